@@ -0,0 +1,136 @@
+use chrono::{DateTime, FixedOffset};
+use language_tags::LanguageTag;
+use url::Url;
+
+use crate::{ParseError, SecurityTxt};
+
+/// Builds a [`SecurityTxt`] field by field.
+///
+/// Unlike constructing the fields directly, invariants required by the RFC
+/// (at least one `Contact`, exactly one `Expires`) are only enforced once,
+/// at [`Self::build`], rather than by many fallible setters.
+#[derive(Debug, Default)]
+pub struct Builder {
+    acknowledgments: Vec<Url>,
+    canonical: Vec<Url>,
+    contacts: Vec<Url>,
+    encryptions: Vec<Url>,
+    expires: Option<DateTime<FixedOffset>>,
+    hiring: Vec<Url>,
+    policies: Vec<Url>,
+    preferred_languages: Vec<LanguageTag>,
+    extensions: Vec<(String, String)>,
+}
+
+impl Builder {
+    /// Add an `Acknowledgments` URL.
+    pub fn acknowledgments(mut self, url: Url) -> Self {
+        self.acknowledgments.push(url);
+        self
+    }
+
+    /// Add a `Canonical` URL.
+    pub fn canonical(mut self, url: Url) -> Self {
+        self.canonical.push(url);
+        self
+    }
+
+    /// Add a `Contact` URL. At least one is required.
+    pub fn contact(mut self, url: Url) -> Self {
+        self.contacts.push(url);
+        self
+    }
+
+    /// Add an `Encryption` URL.
+    pub fn encryption(mut self, url: Url) -> Self {
+        self.encryptions.push(url);
+        self
+    }
+
+    /// Set the `Expires` date. Required, and overwrites any previous value.
+    pub fn expires(mut self, expires: DateTime<FixedOffset>) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Add a `Hiring` URL.
+    pub fn hiring(mut self, url: Url) -> Self {
+        self.hiring.push(url);
+        self
+    }
+
+    /// Add a `Policy` URL.
+    pub fn policy(mut self, url: Url) -> Self {
+        self.policies.push(url);
+        self
+    }
+
+    /// Add a `Preferred-Languages` tag.
+    pub fn preferred_language(mut self, tag: LanguageTag) -> Self {
+        self.preferred_languages.push(tag);
+        self
+    }
+
+    /// Add an extension field.
+    pub fn extension(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extensions.push((name.into(), value.into()));
+        self
+    }
+
+    /// Build the [`SecurityTxt`], checking that at least one `Contact` and
+    /// exactly one `Expires` were provided.
+    pub fn build(self) -> Result<SecurityTxt, ParseError> {
+        let mut contacts = self.contacts.into_iter();
+        let first_contact = contacts.next().ok_or(ParseError::MissingContact)?;
+        let expires = self.expires.ok_or(ParseError::MissingExpires)?;
+
+        Ok(SecurityTxt {
+            acknowledgments: self.acknowledgments,
+            canonical: self.canonical,
+            contacts: (first_contact, contacts.collect()),
+            encryptions: self.encryptions,
+            expires,
+            hiring: self.hiring,
+            policies: self.policies,
+            preferred_languages: self.preferred_languages,
+            extensions: self.extensions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn build_requires_contact_and_expires() {
+        assert_eq!(
+            Err(ParseError::MissingContact),
+            SecurityTxt::builder().build()
+        );
+
+        assert_eq!(
+            Err(ParseError::MissingExpires),
+            SecurityTxt::builder()
+                .contact(Url::parse("mailto:foo@example.com").unwrap())
+                .build()
+        );
+    }
+
+    #[test]
+    fn build_then_display_round_trips() {
+        let txt = SecurityTxt::builder()
+            .contact(Url::parse("mailto:foo@example.com").unwrap())
+            .expires(DateTime::parse_from_rfc3339("2099-01-01T00:00:00Z").unwrap())
+            .extension("X-Foo", "bar")
+            .build()
+            .unwrap();
+
+        assert_eq!(txt.to_string().lines().last().unwrap(), "X-Foo: bar");
+        assert_eq!(
+            SecurityTxt::from_str(&txt.to_string()).unwrap(),
+            txt
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Fetching `security.txt` over HTTPS, behind the `fetch` feature.
+//!
+//! This implements the transport rules from RFC 9116 §3: HTTPS only, the
+//! well-known location first with a fallback to the legacy top-level one,
+//! a `text/plain` content type, and a size cap to keep a misbehaving or
+//! hostile server from turning a fetch into a memory-exhaustion vector.
+
+use core::str::FromStr;
+use std::fmt;
+use std::io::Read;
+
+use crate::{ParseError, SecurityTxt, MIMETYPE, WELL_KNOWN_PATH};
+
+/// The legacy top-level location, kept only as a fallback per RFC 9116's
+/// guidance for backwards compatibility with `draft-foudil-securitytxt`.
+const LEGACY_PATH: &str = "/security.txt";
+
+/// Responses larger than this are rejected outright.
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// An error that can occur while fetching and parsing a `security.txt`.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request itself failed (DNS, TLS, connection, timeout, ...).
+    Http(reqwest::Error),
+    /// Reading the response body failed.
+    Io(std::io::Error),
+    /// The server returned neither a success nor a "not found" status.
+    Status(reqwest::StatusCode),
+    /// The response's `Content-Type` wasn't `text/plain`.
+    UnexpectedContentType(String),
+    /// The response body exceeded [`MAX_RESPONSE_BYTES`].
+    TooLarge(u64),
+    /// Neither the well-known nor the legacy location returned the file.
+    NotFound,
+    /// The fetched file didn't parse.
+    Parse(ParseError),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "request failed: {}", e),
+            Self::Io(e) => write!(f, "failed to read response body: {}", e),
+            Self::Status(status) => write!(f, "unexpected HTTP status: {}", status),
+            Self::UnexpectedContentType(content_type) => {
+                write!(f, "expected `{}`, got `{}`", MIMETYPE, content_type)
+            }
+            Self::TooLarge(len) => write!(
+                f,
+                "response of {} bytes exceeds the {} byte limit",
+                len, MAX_RESPONSE_BYTES
+            ),
+            Self::NotFound => write!(
+                f,
+                "no security.txt found at `{}` or `{}`",
+                WELL_KNOWN_PATH, LEGACY_PATH
+            ),
+            Self::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http(source)
+    }
+}
+
+/// Fetch and parse the `security.txt` published by `domain`.
+///
+/// Tries [`WELL_KNOWN_PATH`] first, falling back to the legacy top-level
+/// `/security.txt` if that's a 404, per RFC 9116. Only ever connects over
+/// HTTPS, and follows redirects as long as they stay on `domain`.
+pub fn fetch(domain: &str) -> Result<SecurityTxt, FetchError> {
+    let client = reqwest::blocking::Client::builder()
+        .https_only(true)
+        .redirect(same_domain_redirect_policy(domain.to_owned()))
+        .build()?;
+
+    match fetch_path(&client, domain, WELL_KNOWN_PATH) {
+        Err(FetchError::Status(reqwest::StatusCode::NOT_FOUND)) => {
+            match fetch_path(&client, domain, LEGACY_PATH) {
+                Err(FetchError::Status(reqwest::StatusCode::NOT_FOUND)) => {
+                    Err(FetchError::NotFound)
+                }
+                result => result,
+            }
+        }
+        result => result,
+    }
+}
+
+/// A redirect policy that only follows redirects whose target host matches
+/// `domain` exactly.
+///
+/// This is a simplification of RFC 9116's "same registered domain" rule: a
+/// full implementation would compare registrable domains (via the public
+/// suffix list) rather than exact hosts, allowing e.g. a redirect from
+/// `example.com` to `www.example.com`.
+fn same_domain_redirect_policy(domain: String) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.url().host_str() == Some(domain.as_str()) {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    })
+}
+
+fn fetch_path(
+    client: &reqwest::blocking::Client,
+    domain: &str,
+    path: &str,
+) -> Result<SecurityTxt, FetchError> {
+    fetch_url(client, &format!("https://{}{}", domain, path))
+}
+
+fn fetch_url(client: &reqwest::blocking::Client, url: &str) -> Result<SecurityTxt, FetchError> {
+    let mut response = client.get(url).send()?;
+
+    if !response.status().is_success() {
+        return Err(FetchError::Status(response.status()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with(MIMETYPE) {
+        return Err(FetchError::UnexpectedContentType(content_type.to_owned()));
+    }
+
+    if let Some(len) = response.content_length() {
+        if len > MAX_RESPONSE_BYTES {
+            return Err(FetchError::TooLarge(len));
+        }
+    }
+
+    let mut body = String::new();
+    response
+        .by_ref()
+        .take(MAX_RESPONSE_BYTES + 1)
+        .read_to_string(&mut body)
+        .map_err(FetchError::Io)?;
+    if body.len() as u64 > MAX_RESPONSE_BYTES {
+        return Err(FetchError::TooLarge(body.len() as u64));
+    }
+
+    SecurityTxt::from_str(&body).map_err(FetchError::Parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::{SocketAddr, TcpListener};
+
+    /// Serve `responses` in order, one per accepted connection, and return
+    /// the address to connect to.
+    fn mock_server(responses: Vec<&'static str>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                // We don't care about the request itself, just enough of
+                // it to unblock the client's write buffer.
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn unexpected_content_type_is_rejected() {
+        let addr = mock_server(vec![
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: 2\r\n\
+             Connection: close\r\n\
+             \r\n\
+             {}",
+        ]);
+        let client = reqwest::blocking::Client::new();
+        let result = fetch_url(&client, &format!("http://{}/security.txt", addr));
+        assert!(matches!(result, Err(FetchError::UnexpectedContentType(_))));
+    }
+
+    #[test]
+    fn oversized_content_length_is_rejected() {
+        let addr = mock_server(vec![
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Length: 1000000\r\n\
+             Connection: close\r\n\
+             \r\n\
+             Contact: mailto:foo@example.com\n",
+        ]);
+        let client = reqwest::blocking::Client::new();
+        let result = fetch_url(&client, &format!("http://{}/security.txt", addr));
+        assert!(matches!(result, Err(FetchError::TooLarge(1_000_000))));
+    }
+
+    #[test]
+    fn redirect_to_same_host_is_followed() {
+        let addr = mock_server(vec![
+            "HTTP/1.1 302 Found\r\n\
+             Location: /other\r\n\
+             Connection: close\r\n\
+             \r\n",
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Length: 62\r\n\
+             Connection: close\r\n\
+             \r\n\
+             Contact: mailto:foo@example.com\nExpires: 2099-01-01T00:00:00Z\n",
+        ]);
+        let client = reqwest::blocking::Client::builder()
+            .redirect(same_domain_redirect_policy(addr.ip().to_string()))
+            .build()
+            .unwrap();
+        let result = fetch_url(&client, &format!("http://{}/security.txt", addr));
+        assert!(result.is_ok(), "expected success, got {:?}", result);
+    }
+
+    #[test]
+    fn redirect_to_different_host_is_not_followed() {
+        let addr = mock_server(vec![
+            "HTTP/1.1 302 Found\r\n\
+             Location: http://example.invalid/other\r\n\
+             Connection: close\r\n\
+             \r\n",
+        ]);
+        let client = reqwest::blocking::Client::builder()
+            .redirect(same_domain_redirect_policy(addr.ip().to_string()))
+            .build()
+            .unwrap();
+        let result = fetch_url(&client, &format!("http://{}/security.txt", addr));
+        assert!(matches!(
+            result,
+            Err(FetchError::Status(reqwest::StatusCode::FOUND))
+        ));
+    }
+}
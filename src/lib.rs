@@ -7,6 +7,16 @@ use std::error::Error;
 use std::fmt;
 use url::Url;
 
+mod builder;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod signed;
+
+pub use builder::Builder;
+#[cfg(feature = "fetch")]
+pub use fetch::{fetch, FetchError};
+pub use signed::{parse_signed, Signature};
+
 /// The conventional name of the file.
 pub const FILENAME: &str = "security.txt";
 
@@ -16,19 +26,42 @@ pub const WELL_KNOWN_PATH: &str = "/.well-known/security.txt";
 /// The required file format of the "security.txt" file (MUST be plain text).
 pub const MIMETYPE: &str = "text/plain";
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Field {
-    Acknowledgments(Url), // Required HTTPS?
-    Canonical(Url),       // Required HTTPS?
+    Acknowledgments(Url), // Scheme SHOULD be https, checked by `SecurityTxt::validate`
+    Canonical(Url),       // Scheme SHOULD be https, checked by `SecurityTxt::validate`
     Contact(Url),
     Encryption(Url),
     Expires(DateTime<FixedOffset>), // Must appear only once
-    Hiring(Url),                    // Required HTTPS?
-    Policy(Url),
+    Hiring(Url), // Scheme SHOULD be https, checked by `SecurityTxt::validate`
+    Policy(Url), // Scheme SHOULD be https, checked by `SecurityTxt::validate`
     PreferredLanguages(Vec<LanguageTag>), // Must appear only once
     Extension(String, String),
 }
 
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Acknowledgments(url) => write!(f, "Acknowledgments: {}", url),
+            Self::Canonical(url) => write!(f, "Canonical: {}", url),
+            Self::Contact(url) => write!(f, "Contact: {}", url),
+            Self::Encryption(url) => write!(f, "Encryption: {}", url),
+            Self::Expires(dt) => write!(f, "Expires: {}", dt.to_rfc3339()),
+            Self::Hiring(url) => write!(f, "Hiring: {}", url),
+            Self::Policy(url) => write!(f, "Policy: {}", url),
+            Self::PreferredLanguages(tags) => write!(
+                f,
+                "Preferred-Languages: {}",
+                tags.iter()
+                    .map(|tag| tag.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Extension(name, value) => write!(f, "{}: {}", name, value),
+        }
+    }
+}
+
 fn split_at_str(string: &str, pattern: char) -> Option<(&str, &str)> {
     let mut split = string.splitn(2, pattern);
     let first = split.next().unwrap();
@@ -38,64 +71,237 @@ fn split_at_str(string: &str, pattern: char) -> Option<(&str, &str)> {
     }
 }
 
-fn parse_rfc5322_datetime(string: &str) -> chrono::ParseResult<DateTime<FixedOffset>> {
-    // TODO: See https://tools.ietf.org/html/rfc5322#section-3.3
-    DateTime::parse_from_str(string, "")
+fn parse_rfc3339_datetime(string: &str) -> chrono::ParseResult<DateTime<FixedOffset>> {
+    // RFC 9116 requires the `Expires` value to be an ISO 8601 / RFC 3339
+    // timestamp, not an RFC 5322 one.
+    DateTime::parse_from_rfc3339(string.trim())
 }
 
-impl FromStr for Field {
-    type Err = ParseError;
-    fn from_str(string: &str) -> Result<Self, Self::Err> {
+impl Field {
+    /// Parse a single line, reporting errors as if they occurred on `line` 1.
+    ///
+    /// Callers that already know which line of a larger document this is
+    /// (e.g. [`parse_fields`]) should relocate the resulting error with
+    /// [`ParseError::relocate`].
+    fn parse_line(string: &str) -> Result<Self, ParseError> {
         if let Some((name, value)) = split_at_str(string, ':') {
+            // 1-based column of the first non-whitespace character of
+            // `value`, skipping e.g. the space after "Name: ".
+            let leading_whitespace = value.len() - value.trim_start().len();
+            let column = name.len() + 2 + leading_whitespace;
             return Ok(match &*name.to_lowercase() {
-                "acknowledgments" => Self::Acknowledgments(Url::parse(value)?),
-                "canonical" => Self::Canonical(Url::parse(value)?),
-                "contact" => Self::Contact(Url::parse(value)?),
-                "encryption" => Self::Encryption(Url::parse(value)?),
-                "expires" => Self::Expires(parse_rfc5322_datetime(value)?),
-                "hiring" => Self::Hiring(Url::parse(value)?),
-                "policy" => Self::Policy(Url::parse(value)?),
+                "acknowledgments" => Self::Acknowledgments(
+                    Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?,
+                ),
+                "canonical" => {
+                    Self::Canonical(Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?)
+                }
+                "contact" => {
+                    Self::Contact(Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?)
+                }
+                "encryption" => {
+                    Self::Encryption(Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?)
+                }
+                "expires" => Self::Expires(parse_rfc3339_datetime(value).map_err(|source| {
+                    ParseError::InvalidDate {
+                        source,
+                        line: 1,
+                        column,
+                    }
+                })?),
+                "hiring" => {
+                    Self::Hiring(Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?)
+                }
+                "policy" => {
+                    Self::Policy(Url::parse(value).map_err(|source| ParseError::InvalidUrl {
+                        source,
+                        line: 1,
+                        column,
+                    })?)
+                }
                 "preferred-languages" => {
                     let languages = value
                         .split(',')
-                        .map(|s| LanguageTag::from_str(s))
-                        .collect::<Result<_, _>>()?;
+                        .map(|tag| LanguageTag::from_str(tag.trim()))
+                        .collect::<Result<_, _>>()
+                        .map_err(|source| ParseError::InvalidLanguageTag {
+                            source,
+                            line: 1,
+                            column,
+                        })?;
                     Self::PreferredLanguages(languages)
                 }
-                _ => Self::Extension(name.into(), value.into()),
+                _ => Self::Extension(name.into(), value.trim().into()),
             });
         }
-        Err(ParseError("Missing `:`".into()))
+        Err(ParseError::MissingColon {
+            line: 1,
+            column: string.len() + 1,
+        })
+    }
+}
+
+impl FromStr for Field {
+    type Err = ParseError;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::parse_line(string)
     }
 }
 
-/// Signifies an error in the specification
+/// Signifies an error in the specification.
+///
+/// Each variant that originates from a single line carries the 1-based
+/// `line` and `column` at which the problem was found, so callers can point
+/// users at the exact location instead of re-scanning the whole file.
 #[derive(Debug, PartialEq)]
-pub struct ParseError(String);
+pub enum ParseError {
+    /// A field line contained no `:` separator.
+    MissingColon { line: usize, column: usize },
+    /// A URL-valued field could not be parsed as a URL.
+    InvalidUrl {
+        source: url::ParseError,
+        line: usize,
+        column: usize,
+    },
+    /// The `Expires` field could not be parsed as a date.
+    InvalidDate {
+        source: chrono::format::ParseError,
+        line: usize,
+        column: usize,
+    },
+    /// A `Preferred-Languages` entry was not a valid language tag.
+    InvalidLanguageTag {
+        source: language_tags::Error,
+        line: usize,
+        column: usize,
+    },
+    /// A field that must appear at most once appeared more than once.
+    ///
+    /// No `column` here: the duplicate is the whole field, not a value
+    /// inside it, so `line` is all a caller can point at.
+    DuplicateField { name: &'static str, line: usize },
+    /// There was no `Contact` field.
+    MissingContact,
+    /// There was no `Expires` field.
+    MissingExpires,
+}
+
+impl ParseError {
+    /// Move a single-line error onto `line` of a larger document.
+    ///
+    /// No-op for document-level errors ([`Self::MissingContact`],
+    /// [`Self::MissingExpires`]) that aren't tied to one line.
+    fn relocate(self, line: usize) -> Self {
+        match self {
+            Self::MissingColon { column, .. } => Self::MissingColon { line, column },
+            Self::InvalidUrl { source, column, .. } => Self::InvalidUrl {
+                source,
+                line,
+                column,
+            },
+            Self::InvalidDate { source, column, .. } => Self::InvalidDate {
+                source,
+                line,
+                column,
+            },
+            Self::InvalidLanguageTag { source, column, .. } => Self::InvalidLanguageTag {
+                source,
+                line,
+                column,
+            },
+            Self::DuplicateField { name, .. } => Self::DuplicateField { name, line },
+            Self::MissingContact => Self::MissingContact,
+            Self::MissingExpires => Self::MissingExpires,
+        }
+    }
+}
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Self::MissingColon { line, column } => write!(f, "{}:{}: missing `:`", line, column),
+            Self::InvalidUrl {
+                source,
+                line,
+                column,
+            } => write!(f, "{}:{}: invalid URL: {}", line, column, source),
+            Self::InvalidDate {
+                source,
+                line,
+                column,
+            } => write!(f, "{}:{}: invalid date: {}", line, column, source),
+            Self::InvalidLanguageTag {
+                source,
+                line,
+                column,
+            } => write!(f, "{}:{}: invalid language tag: {}", line, column, source),
+            Self::DuplicateField { name, line } => {
+                write!(f, "line {}: the `{}` field must only appear once", line, name)
+            }
+            Self::MissingContact => write!(f, "must have at least one `Contact` field"),
+            Self::MissingExpires => write!(f, "must have an `Expires` field"),
+        }
     }
 }
 
-impl Error for ParseError {}
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidUrl { source, .. } => Some(source),
+            Self::InvalidDate { source, .. } => Some(source),
+            Self::InvalidLanguageTag { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
 impl From<language_tags::Error> for ParseError {
-    fn from(error: language_tags::Error) -> Self {
-        ParseError(error.to_string())
+    fn from(source: language_tags::Error) -> Self {
+        ParseError::InvalidLanguageTag {
+            source,
+            line: 1,
+            column: 1,
+        }
     }
 }
 
 impl From<url::ParseError> for ParseError {
-    fn from(error: url::ParseError) -> Self {
-        ParseError(error.to_string())
+    fn from(source: url::ParseError) -> Self {
+        ParseError::InvalidUrl {
+            source,
+            line: 1,
+            column: 1,
+        }
     }
 }
 
 impl From<chrono::format::ParseError> for ParseError {
-    fn from(error: chrono::format::ParseError) -> Self {
-        ParseError(error.to_string())
+    fn from(source: chrono::format::ParseError) -> Self {
+        ParseError::InvalidDate {
+            source,
+            line: 1,
+            column: 1,
+        }
     }
 }
 
@@ -115,14 +321,26 @@ impl FromStr for Line {
     }
 }
 
-pub fn parse_fields<'a>(string: &'a str) -> impl Iterator<Item = Result<Field, ParseError>> + 'a {
-    string
-        .lines()
-        .filter_map(|line| match Line::from_str(line) {
-            Ok(Line::Field(field)) => Some(Ok(field)),
+/// Parse every line of `string` as a [`Line`], yielding the contained
+/// [`Field`]s (in 1-based line order) alongside which line they came from.
+///
+/// Comments are skipped. Used internally so that [`SecurityTxt::from_str`]
+/// can report the line of a duplicate field.
+fn parse_fields_with_lines<'a>(
+    string: &'a str,
+) -> impl Iterator<Item = (usize, Result<Field, ParseError>)> + 'a {
+    string.lines().enumerate().filter_map(|(i, line)| {
+        let line_number = i + 1;
+        match Line::from_str(line) {
+            Ok(Line::Field(field)) => Some((line_number, Ok(field))),
             Ok(Line::Comment(_)) => None,
-            Err(e) => Some(Err(e)),
-        })
+            Err(e) => Some((line_number, Err(e.relocate(line_number)))),
+        }
+    })
+}
+
+pub fn parse_fields<'a>(string: &'a str) -> impl Iterator<Item = Result<Field, ParseError>> + 'a {
+    parse_fields_with_lines(string).map(|(_, field)| field)
 }
 
 pub fn parse(string: &str) -> Result<SecurityTxt, ParseError> {
@@ -158,7 +376,7 @@ impl FromStr for SecurityTxt {
         let mut preferred_languages = None;
         let mut extensions = vec![];
 
-        for field in parse_fields(string) {
+        for (line, field) in parse_fields_with_lines(string) {
             match field? {
                 Field::Acknowledgments(url) => acknowledgments.push(url),
                 Field::Canonical(url) => canonical.push(url),
@@ -172,7 +390,10 @@ impl FromStr for SecurityTxt {
                 Field::Encryption(url) => encryptions.push(url),
                 Field::Expires(dt) => {
                     if expires.is_some() {
-                        return Err(ParseError("The Expires field must only appear once".into()));
+                        return Err(ParseError::DuplicateField {
+                            name: "Expires",
+                            line,
+                        });
                     } else {
                         expires = Some(dt);
                     }
@@ -181,9 +402,10 @@ impl FromStr for SecurityTxt {
                 Field::Policy(url) => policies.push(url),
                 Field::PreferredLanguages(languages) => {
                     if preferred_languages.is_some() {
-                        return Err(ParseError(
-                            "The Preferred-Languages field must only appear once".into(),
-                        ));
+                        return Err(ParseError::DuplicateField {
+                            name: "Preferred-Languages",
+                            line,
+                        });
                     } else {
                         preferred_languages = Some(languages)
                     }
@@ -192,12 +414,11 @@ impl FromStr for SecurityTxt {
             }
         }
 
-        let contacts =
-            contacts.ok_or_else(|| ParseError("Must have at least one Contact field".into()))?;
+        let contacts = contacts.ok_or(ParseError::MissingContact)?;
 
-        let expires = expires.ok_or_else(|| ParseError("Must have an Expires field".into()))?;
+        let expires = expires.ok_or(ParseError::MissingExpires)?;
 
-        let preferred_languages = preferred_languages.unwrap_or_else(|| vec![]);
+        let preferred_languages = preferred_languages.unwrap_or_default();
 
         Ok(Self {
             acknowledgments,
@@ -213,6 +434,179 @@ impl FromStr for SecurityTxt {
     }
 }
 
+impl fmt::Display for SecurityTxt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Contacts first, per RFC 9116's example layout.
+        let (first_contact, rest_contacts) = &self.contacts;
+        writeln!(f, "{}", Field::Contact(first_contact.clone()))?;
+        for url in rest_contacts {
+            writeln!(f, "{}", Field::Contact(url.clone()))?;
+        }
+        for url in &self.acknowledgments {
+            writeln!(f, "{}", Field::Acknowledgments(url.clone()))?;
+        }
+        for url in &self.canonical {
+            writeln!(f, "{}", Field::Canonical(url.clone()))?;
+        }
+        for url in &self.encryptions {
+            writeln!(f, "{}", Field::Encryption(url.clone()))?;
+        }
+        writeln!(f, "{}", Field::Expires(self.expires))?;
+        for url in &self.hiring {
+            writeln!(f, "{}", Field::Hiring(url.clone()))?;
+        }
+        for url in &self.policies {
+            writeln!(f, "{}", Field::Policy(url.clone()))?;
+        }
+        if !self.preferred_languages.is_empty() {
+            writeln!(
+                f,
+                "{}",
+                Field::PreferredLanguages(self.preferred_languages.clone())
+            )?;
+        }
+        for (name, value) in &self.extensions {
+            writeln!(f, "{}", Field::Extension(name.clone(), value.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+/// A file is considered stale if its `Expires` date is further out than this,
+/// per RFC 9116's recommendation to keep the field close to the present.
+fn max_recommended_validity() -> chrono::Duration {
+    chrono::Duration::days(365)
+}
+
+/// A non-fatal observation about an otherwise well-formed [`SecurityTxt`].
+///
+/// Unlike [`ParseError`], a [`Warning`] doesn't prevent a file from being
+/// parsed; it's for tools (linters, monitors) that want to flag files that
+/// are technically valid but don't follow the RFC's recommendations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The `Expires` date is more than a year in the future, which risks the
+    /// file being forgotten and going stale.
+    ExpiresFarInFuture { expires: DateTime<FixedOffset> },
+    /// A URL-valued field used a scheme other than what RFC 9116
+    /// recommends, e.g. a `Canonical` URL that isn't `https://`.
+    UnexpectedScheme {
+        field: &'static str,
+        url: Url,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ExpiresFarInFuture { expires } => write!(
+                f,
+                "`Expires` is set to {}, more than a year in the future",
+                expires.to_rfc3339()
+            ),
+            Self::UnexpectedScheme { field, url, reason } => {
+                write!(f, "`{}` field has URL `{}`: {}", field, url, reason)
+            }
+        }
+    }
+}
+
+/// Checks that `url`'s scheme is `https`, per RFC 9116's recommendation for
+/// `Canonical`, `Acknowledgments`, `Hiring`, and `Policy`.
+fn check_https_scheme(field: &'static str, url: &Url, warnings: &mut Vec<Warning>) {
+    if url.scheme() != "https" {
+        warnings.push(Warning::UnexpectedScheme {
+            field,
+            url: url.clone(),
+            reason: "should use the `https` scheme",
+        });
+    }
+}
+
+/// Checks that a `Contact` URL uses `https`, `mailto`, or `tel`, the only
+/// schemes RFC 9116 gives examples of.
+fn check_contact_scheme(url: &Url, warnings: &mut Vec<Warning>) {
+    match url.scheme() {
+        "https" | "mailto" | "tel" => {}
+        _ => warnings.push(Warning::UnexpectedScheme {
+            field: "Contact",
+            url: url.clone(),
+            reason: "expected the `https`, `mailto`, or `tel` scheme",
+        }),
+    }
+}
+
+impl SecurityTxt {
+    /// Start building a [`SecurityTxt`] from scratch.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// The date after which this file should no longer be considered valid.
+    pub fn expires(&self) -> DateTime<FixedOffset> {
+        self.expires
+    }
+
+    /// Whether the file's `Expires` date has already passed.
+    pub fn is_expired(&self) -> bool {
+        self.time_until_expiry() < chrono::Duration::zero()
+    }
+
+    /// How long until the file's `Expires` date is reached.
+    ///
+    /// Negative if the file has already expired.
+    pub fn time_until_expiry(&self) -> chrono::Duration {
+        self.expires.signed_duration_since(Utc::now())
+    }
+
+    /// Run every non-fatal check against this file, collecting the results.
+    ///
+    /// This is separate from parsing itself: a malformed-but-present file
+    /// still parses, and callers choose whether to treat these warnings as
+    /// fatal (strict mode) or merely informational (lenient mode).
+    pub fn validate(&self) -> Vec<Warning> {
+        let mut warnings = self.expiry_warnings();
+        warnings.extend(self.scheme_warnings());
+        warnings
+    }
+
+    /// Non-fatal warnings about URL-valued fields using an unexpected
+    /// scheme, e.g. a `Canonical` URL that isn't `https://`.
+    pub fn scheme_warnings(&self) -> Vec<Warning> {
+        let mut warnings = vec![];
+        for url in &self.canonical {
+            check_https_scheme("Canonical", url, &mut warnings);
+        }
+        for url in &self.acknowledgments {
+            check_https_scheme("Acknowledgments", url, &mut warnings);
+        }
+        for url in &self.hiring {
+            check_https_scheme("Hiring", url, &mut warnings);
+        }
+        for url in &self.policies {
+            check_https_scheme("Policy", url, &mut warnings);
+        }
+        check_contact_scheme(&self.contacts.0, &mut warnings);
+        for url in &self.contacts.1 {
+            check_contact_scheme(url, &mut warnings);
+        }
+        warnings
+    }
+
+    /// Non-fatal warnings about the `Expires` field, such as it being set
+    /// further in the future than RFC 9116 recommends.
+    pub fn expiry_warnings(&self) -> Vec<Warning> {
+        let mut warnings = vec![];
+        if self.time_until_expiry() > max_recommended_validity() {
+            warnings.push(Warning::ExpiresFarInFuture {
+                expires: self.expires,
+            });
+        }
+        warnings
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,4 +619,76 @@ mod tests {
             Field::from_str("Acknowledgments:https://abc.com")
         );
     }
+
+    #[test]
+    fn missing_colon_reports_location() {
+        assert_eq!(
+            Err(ParseError::MissingColon { line: 1, column: 9 }),
+            Field::from_str("no-colon")
+        );
+    }
+
+    #[test]
+    fn invalid_url_column_skips_the_space_after_the_colon() {
+        match Field::from_str("Contact: not a url") {
+            Err(ParseError::InvalidUrl { column, .. }) => assert_eq!(column, 10),
+            other => panic!("expected InvalidUrl, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicate_preferred_languages_reports_line() {
+        let input = "Preferred-Languages: en\n\
+                     Preferred-Languages: en\n";
+        match SecurityTxt::from_str(input) {
+            Err(ParseError::DuplicateField { name, line }) => {
+                assert_eq!(name, "Preferred-Languages");
+                assert_eq!(line, 2);
+            }
+            other => panic!("expected DuplicateField, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expires_is_rfc3339_not_rfc5322() {
+        assert_eq!(
+            Ok(Field::Expires(
+                DateTime::parse_from_rfc3339("2030-01-01T00:00:00Z").unwrap()
+            )),
+            Field::from_str("Expires:2030-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn far_future_expiry_is_warned_about() {
+        let input = "Contact: mailto:foo@example.com\nExpires: 2099-01-01T00:00:00Z\n";
+        let txt = SecurityTxt::from_str(input).unwrap();
+        assert!(!txt.expiry_warnings().is_empty());
+        assert!(!txt.is_expired());
+    }
+
+    #[test]
+    fn http_canonical_url_is_warned_about() {
+        let input = "Contact: mailto:foo@example.com\n\
+                     Expires: 2030-01-01T00:00:00Z\n\
+                     Canonical: http://example.com/security.txt\n";
+        let txt = SecurityTxt::from_str(input).unwrap();
+        assert_eq!(
+            vec![Warning::UnexpectedScheme {
+                field: "Canonical",
+                url: Url::parse("http://example.com/security.txt").unwrap(),
+                reason: "should use the `https` scheme",
+            }],
+            txt.scheme_warnings()
+        );
+    }
+
+    #[test]
+    fn mailto_and_tel_contacts_are_accepted() {
+        let input = "Contact: mailto:foo@example.com\n\
+                     Contact: tel:+1-201-555-0123\n\
+                     Expires: 2030-01-01T00:00:00Z\n";
+        let txt = SecurityTxt::from_str(input).unwrap();
+        assert!(txt.scheme_warnings().is_empty());
+    }
 }
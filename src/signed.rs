@@ -0,0 +1,246 @@
+//! Support for OpenPGP cleartext-signed `security.txt` files.
+//!
+//! RFC 9116 recommends that operators sign their file using an [OpenPGP
+//! cleartext signature][cleartext], which wraps the canonical message in
+//! `-----BEGIN PGP SIGNED MESSAGE-----` / `-----BEGIN PGP SIGNATURE-----`
+//! armor and dash-escapes any body line that would otherwise be mistaken
+//! for armor.
+//!
+//! [cleartext]: https://www.rfc-editor.org/rfc/rfc4880#section-7
+
+use core::str::FromStr;
+
+use crate::{ParseError, SecurityTxt};
+
+const BEGIN_SIGNED_MESSAGE: &str = "-----BEGIN PGP SIGNED MESSAGE-----";
+const BEGIN_SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----";
+const END_SIGNATURE: &str = "-----END PGP SIGNATURE-----";
+
+/// The armored OpenPGP signature accompanying a cleartext-signed
+/// `security.txt`, as produced by e.g. `gpg --clearsign`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    /// The raw armor, including the begin/end markers.
+    armored: String,
+}
+
+impl Signature {
+    /// The raw `-----BEGIN PGP SIGNATURE-----` armor bytes.
+    pub fn armored(&self) -> &str {
+        &self.armored
+    }
+
+    /// Verify this signature against `cleartext` using the given OpenPGP
+    /// certificate.
+    ///
+    /// A cleartext-signed message carries a *detached* signature (the
+    /// armor block only covers the signature, not the message it signs),
+    /// so this uses sequoia's `DetachedVerifierBuilder` rather than the
+    /// combined-message `VerifierBuilder`.
+    ///
+    /// Requires the `openpgp` feature, which pulls in `sequoia-openpgp`.
+    #[cfg(feature = "openpgp")]
+    pub fn verify(
+        &self,
+        cleartext: &str,
+        cert: &sequoia_openpgp::Cert,
+    ) -> sequoia_openpgp::Result<()> {
+        use sequoia_openpgp::parse::stream::{
+            DetachedVerifierBuilder, MessageLayer, MessageStructure, VerificationHelper,
+        };
+        use sequoia_openpgp::parse::Parse;
+        use sequoia_openpgp::policy::StandardPolicy;
+        use sequoia_openpgp::{Cert, KeyHandle, Result};
+
+        struct Helper<'a>(&'a Cert);
+
+        impl<'a> VerificationHelper for Helper<'a> {
+            fn get_certs(&mut self, _ids: &[KeyHandle]) -> Result<Vec<Cert>> {
+                Ok(vec![self.0.clone()])
+            }
+
+            fn check(&mut self, structure: MessageStructure) -> Result<()> {
+                for layer in structure.into_iter() {
+                    if let MessageLayer::SignatureGroup { results } = layer {
+                        if !results.into_iter().any(|result| result.is_ok()) {
+                            return Err(anyhow::anyhow!(
+                                "no valid signature from the given certificate"
+                            ));
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        let policy = StandardPolicy::new();
+        let mut verifier = DetachedVerifierBuilder::from_bytes(self.armored.as_bytes())?
+            .with_policy(&policy, None, Helper(cert))?;
+        verifier.verify_bytes(cleartext.as_bytes())
+    }
+}
+
+/// Recover the canonical message body and raw signature armor from a
+/// cleartext-signed document, or return `None` if `string` isn't one.
+fn strip_cleartext_armor(string: &str) -> Option<(String, String)> {
+    let mut lines = string.lines();
+    if lines.next()?.trim() != BEGIN_SIGNED_MESSAGE {
+        return None;
+    }
+
+    // Skip the "Hash: ..." armor headers up to the blank separator line.
+    for line in &mut lines {
+        if line.is_empty() {
+            break;
+        }
+    }
+
+    let mut body = String::new();
+    let mut signature = String::new();
+    let mut in_signature = false;
+    for line in lines {
+        if line == BEGIN_SIGNATURE {
+            in_signature = true;
+        }
+        if in_signature {
+            signature.push_str(line);
+            signature.push('\n');
+            if line == END_SIGNATURE {
+                break;
+            }
+        } else {
+            // Lines beginning with "- " are dash-escaped in the original.
+            // The cleartext signature framework (RFC 4880 §7.1) also
+            // canonicalizes away trailing whitespace on every line before
+            // hashing, so it must be stripped here too or a genuinely
+            // signed file with trailing whitespace won't verify.
+            let line = line.strip_prefix("- ").unwrap_or(line);
+            body.push_str(line.trim_end());
+            body.push('\n');
+        }
+    }
+
+    Some((body, signature))
+}
+
+/// Parse `string`, transparently unwrapping an OpenPGP cleartext signature
+/// if one is present.
+///
+/// Returns the parsed [`SecurityTxt`] alongside the [`Signature`], if any.
+/// The signature is not verified; call [`Signature::verify`] with the
+/// expected public key to do so.
+pub fn parse_signed(string: &str) -> Result<(SecurityTxt, Option<Signature>), ParseError> {
+    match strip_cleartext_armor(string) {
+        Some((body, armored)) => {
+            let txt = SecurityTxt::from_str(&body)?;
+            Ok((txt, Some(Signature { armored })))
+        }
+        None => Ok((SecurityTxt::from_str(string)?, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_armor_and_dash_escaping() {
+        let input = "-----BEGIN PGP SIGNED MESSAGE-----\n\
+                     Hash: SHA256\n\
+                     \n\
+                     Contact: mailto:foo@example.com\n\
+                     - # not a real comment, just dash-escaped\n\
+                     Expires: 2099-01-01T00:00:00Z\n\
+                     -----BEGIN PGP SIGNATURE-----\n\
+                     \n\
+                     abcdefg\n\
+                     -----END PGP SIGNATURE-----\n";
+
+        let (txt, signature) = parse_signed(input).unwrap();
+        assert_eq!(txt.contacts.0.as_str(), "mailto:foo@example.com");
+        let signature = signature.expect("should be signed");
+        assert!(signature.armored().starts_with(BEGIN_SIGNATURE));
+        assert!(signature.armored().contains("abcdefg"));
+    }
+
+    #[test]
+    fn trailing_whitespace_is_stripped_from_body_lines() {
+        let input = "-----BEGIN PGP SIGNED MESSAGE-----\n\
+                     Hash: SHA256\n\
+                     \n\
+                     Contact: mailto:foo@example.com   \n\
+                     Expires: 2099-01-01T00:00:00Z\n\
+                     -----BEGIN PGP SIGNATURE-----\n\
+                     \n\
+                     abcdefg\n\
+                     -----END PGP SIGNATURE-----\n";
+
+        let (body, _) = strip_cleartext_armor(input).unwrap();
+        assert!(body.contains("Contact: mailto:foo@example.com\n"));
+        assert!(!body.contains("   \n"));
+    }
+
+    #[test]
+    fn plain_file_has_no_signature() {
+        let input = "Contact: mailto:foo@example.com\nExpires: 2099-01-01T00:00:00Z\n";
+        let (_, signature) = parse_signed(input).unwrap();
+        assert!(signature.is_none());
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn verify_accepts_genuine_signature_and_rejects_tampering() {
+        use sequoia_openpgp::cert::CertBuilder;
+        use sequoia_openpgp::policy::StandardPolicy;
+        use sequoia_openpgp::serialize::stream::{Message, Signer};
+        use std::io::Write;
+
+        let (cert, _) = CertBuilder::new().add_signing_subkey().generate().unwrap();
+        let policy = StandardPolicy::new();
+        let keypair = cert
+            .keys()
+            .unencrypted_secret()
+            .with_policy(&policy, None)
+            .for_signing()
+            .next()
+            .unwrap()
+            .key()
+            .clone()
+            .into_keypair()
+            .unwrap();
+
+        let cleartext = "Contact: mailto:foo@example.com\nExpires: 2099-01-01T00:00:00Z\n";
+
+        let mut raw_signature = vec![];
+        {
+            let message = Message::new(&mut raw_signature);
+            let mut signer = Signer::new(message, keypair).detached().build().unwrap();
+            signer.write_all(cleartext.as_bytes()).unwrap();
+            signer.finalize().unwrap();
+        }
+
+        let mut armored = vec![];
+        {
+            let mut writer = sequoia_openpgp::armor::Writer::new(
+                &mut armored,
+                sequoia_openpgp::armor::Kind::Signature,
+            )
+            .unwrap();
+            writer.write_all(&raw_signature).unwrap();
+            writer.finalize().unwrap();
+        }
+
+        let signature = Signature {
+            armored: String::from_utf8(armored).unwrap(),
+        };
+
+        signature
+            .verify(cleartext, &cert)
+            .expect("a genuine signature should verify");
+
+        assert!(
+            signature.verify("tampered contents", &cert).is_err(),
+            "a signature over different contents should not verify"
+        );
+    }
+}